@@ -5,8 +5,10 @@
 
 use crate::client::auth::AuthSchemeId;
 use crate::client::orchestrator::Future;
+use crate::client::runtime_components::RuntimeComponents;
 use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreAppend, StoreReplace};
 use std::any::Any;
+use std::fmt;
 use std::fmt::Debug;
 use std::sync::Arc;
 use std::time::SystemTime;
@@ -14,11 +16,44 @@ use std::time::SystemTime;
 #[cfg(feature = "http-auth")]
 pub mod http;
 
+pub mod cache;
+pub mod chain;
+pub mod secret;
+pub mod validate;
+
 /// Resolves an identity for a request.
 pub trait IdentityResolver: Send + Sync + Debug {
+    fn resolve_identity(
+        &self,
+        runtime_components: &RuntimeComponents,
+        config_bag: &ConfigBag,
+    ) -> Future<Identity>;
+}
+
+/// Migration shim for identity resolvers written before [`IdentityResolver`] took a
+/// [`RuntimeComponents`] parameter.
+///
+/// Implement this trait instead of [`IdentityResolver`] if your resolver only needs the
+/// [`ConfigBag`]; a blanket [`IdentityResolver`] impl is provided that ignores the
+/// `RuntimeComponents` it's given. New resolvers that need shared runtime pieces (the
+/// HTTP client, a `TimeSource`, etc.) should implement [`IdentityResolver`] directly.
+pub trait ConfigBagOnlyIdentityResolver: Send + Sync + Debug {
     fn resolve_identity(&self, config_bag: &ConfigBag) -> Future<Identity>;
 }
 
+impl<T> IdentityResolver for T
+where
+    T: ConfigBagOnlyIdentityResolver,
+{
+    fn resolve_identity(
+        &self,
+        _runtime_components: &RuntimeComponents,
+        config_bag: &ConfigBag,
+    ) -> Future<Identity> {
+        ConfigBagOnlyIdentityResolver::resolve_identity(self, config_bag)
+    }
+}
+
 /// Container for a shared identity resolver.
 #[derive(Clone, Debug)]
 pub struct SharedIdentityResolver(Arc<dyn IdentityResolver>);
@@ -31,8 +66,12 @@ impl SharedIdentityResolver {
 }
 
 impl IdentityResolver for SharedIdentityResolver {
-    fn resolve_identity(&self, config_bag: &ConfigBag) -> Future<Identity> {
-        self.0.resolve_identity(config_bag)
+    fn resolve_identity(
+        &self,
+        runtime_components: &RuntimeComponents,
+        config_bag: &ConfigBag,
+    ) -> Future<Identity> {
+        self.0.resolve_identity(runtime_components, config_bag)
     }
 }
 
@@ -88,15 +127,28 @@ impl IdentityResolvers {
         Self { identity_resolvers }
     }
 
+    /// Returns the identity resolver registered for `scheme_id`.
+    ///
+    /// If more than one resolver was registered for this auth scheme, they're composed
+    /// into a [`chain::ChainIdentityResolver`] that tries each in registration order and
+    /// falls back to the next on failure.
     pub fn identity_resolver(&self, scheme_id: AuthSchemeId) -> Option<SharedIdentityResolver> {
-        self.identity_resolvers
+        let mut matching = self
+            .identity_resolvers
             .iter()
-            .find(|pair| pair.scheme_id() == scheme_id)
-            .map(|pair| pair.identity_resolver())
+            .filter(|pair| pair.scheme_id() == scheme_id)
+            .map(|pair| pair.identity_resolver());
+        let first = matching.next()?;
+        match matching.next() {
+            None => Some(first),
+            Some(second) => Some(SharedIdentityResolver::new(chain::ChainIdentityResolver::new(
+                std::iter::once(first).chain(std::iter::once(second)).chain(matching),
+            ))),
+        }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct Identity {
     data: Arc<dyn Any + Send + Sync>,
     expiration: Option<SystemTime>,
@@ -110,6 +162,15 @@ impl Identity {
         }
     }
 
+    /// Creates a new [`Identity`] whose data is a [`secret::SecretString`] wrapping `secret`.
+    ///
+    /// This is the preferred way to store token/key material (e.g. for `@httpApiKeyAuth`
+    /// or `@httpBearerAuth`) since it ensures the value is zeroized on drop and never
+    /// printed via `Debug`, without the auth scheme having to implement redaction itself.
+    pub fn new_secret(secret: impl Into<String>, expiration: Option<SystemTime>) -> Self {
+        Self::new(secret::SecretString::new(secret), expiration)
+    }
+
     pub fn data<T: 'static>(&self) -> Option<&T> {
         self.data.downcast_ref()
     }
@@ -119,6 +180,17 @@ impl Identity {
     }
 }
 
+impl fmt::Debug for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Never print `data` verbatim: it may hold arbitrary secret material (see
+        // `secret::SecretString`), and this type has no way to know whether the
+        // concrete type stored by a given caller is safe to print.
+        f.debug_struct("Identity")
+            .field("expiration", &self.expiration)
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,4 +222,57 @@ mod tests {
         assert_eq!("bar", identity.data::<MyIdentityData>().unwrap().last);
         assert_eq!(Some(&expiration), identity.expiration());
     }
+
+    #[test]
+    fn debug_never_prints_secret_data() {
+        let identity = Identity::new_secret("super-secret-token", None);
+        let debug_output = format!("{:?}", identity);
+        assert!(!debug_output.contains("super-secret-token"));
+    }
+
+    #[test]
+    fn multiple_resolvers_for_the_same_scheme_compose_into_a_chain() {
+        #[derive(Debug)]
+        struct StubResolver;
+        impl ConfigBagOnlyIdentityResolver for StubResolver {
+            fn resolve_identity(&self, _config_bag: &ConfigBag) -> Future<Identity> {
+                Future::ready(Ok(Identity::new("stub", None)))
+            }
+        }
+
+        let scheme_id = AuthSchemeId::new("test-scheme");
+        let resolvers = IdentityResolvers::new(
+            [
+                ConfiguredIdentityResolver::new(
+                    scheme_id,
+                    SharedIdentityResolver::new(StubResolver),
+                ),
+                ConfiguredIdentityResolver::new(
+                    scheme_id,
+                    SharedIdentityResolver::new(StubResolver),
+                ),
+            ]
+            .iter(),
+        );
+
+        // A single registration is returned as-is...
+        let single = IdentityResolvers::new(
+            [ConfiguredIdentityResolver::new(
+                scheme_id,
+                SharedIdentityResolver::new(StubResolver),
+            )]
+            .iter(),
+        );
+        assert!(single.identity_resolver(scheme_id).is_some());
+        assert!(!format!("{:?}", single.identity_resolver(scheme_id).unwrap())
+            .contains("ChainIdentityResolver"));
+
+        // ...but two or more registrations for the same scheme compose into a chain.
+        assert!(resolvers.identity_resolver(scheme_id).is_some());
+        assert!(format!("{:?}", resolvers.identity_resolver(scheme_id).unwrap())
+            .contains("ChainIdentityResolver"));
+        assert!(resolvers
+            .identity_resolver(AuthSchemeId::new("unregistered-scheme"))
+            .is_none());
+    }
 }
@@ -0,0 +1,333 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A caching wrapper for [`IdentityResolver`] implementations.
+
+use crate::client::identity::{Identity, IdentityResolver, SharedIdentityResolver};
+use crate::client::orchestrator::Future;
+use crate::client::runtime_components::RuntimeComponents;
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_types::config_bag::ConfigBag;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex as AsyncMutex;
+
+/// The default amount of time before expiration that an identity is considered stale
+/// and eligible for refresh.
+const DEFAULT_REFRESH_BUFFER: Duration = Duration::from_secs(60);
+
+/// An [`IdentityResolver`] that caches the identity produced by an inner resolver.
+///
+/// The cached identity is reused for as long as `now + refresh_buffer < expiration`.
+/// Identities with no expiration are cached indefinitely. When the cache is stale,
+/// concurrent callers single-flight the refresh: every caller that misses the cache
+/// calls the inner resolver's `resolve_identity`, but only the caller holding the
+/// refresh lock awaits the future it returns; the rest wait for that refresh to finish
+/// and then return the identity it cached. Work an inner resolver does inside
+/// `resolve_identity` itself, rather than in the future it returns, runs once per
+/// caller that missed the cache. If the refresh fails, the next waiter awaits its own
+/// inner future.
+pub struct CachingIdentityResolver {
+    inner: SharedIdentityResolver,
+    refresh_buffer: Duration,
+    cached: Arc<RwLock<Option<Identity>>>,
+    refresh_lock: Arc<AsyncMutex<()>>,
+}
+
+impl fmt::Debug for CachingIdentityResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachingIdentityResolver")
+            .field("inner", &self.inner)
+            .field("refresh_buffer", &self.refresh_buffer)
+            .finish()
+    }
+}
+
+impl Clone for CachingIdentityResolver {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            refresh_buffer: self.refresh_buffer,
+            cached: self.cached.clone(),
+            refresh_lock: self.refresh_lock.clone(),
+        }
+    }
+}
+
+impl CachingIdentityResolver {
+    /// Creates a [`Builder`] for a [`CachingIdentityResolver`] that wraps `identity_resolver`.
+    pub fn builder(identity_resolver: SharedIdentityResolver) -> Builder {
+        Builder {
+            identity_resolver,
+            refresh_buffer: DEFAULT_REFRESH_BUFFER,
+        }
+    }
+
+    /// Returns the cached identity unless it expires within `refresh_buffer` of `now`.
+    /// A cached identity with no expiration is always returned.
+    fn cached_valid_identity(&self, now: SystemTime) -> Option<Identity> {
+        let cached = self.cached.read().unwrap();
+        let identity = cached.as_ref()?;
+        match identity.expiration() {
+            None => Some(identity.clone()),
+            Some(expiration) if now + self.refresh_buffer < *expiration => Some(identity.clone()),
+            Some(_) => None,
+        }
+    }
+}
+
+impl IdentityResolver for CachingIdentityResolver {
+    fn resolve_identity(
+        &self,
+        runtime_components: &RuntimeComponents,
+        config_bag: &ConfigBag,
+    ) -> Future<Identity> {
+        // Go through the configured `TimeSource` (rather than `SystemTime::now()`
+        // directly) so tests — and callers with unusual clock requirements — can
+        // control what "now" means for expiry math.
+        let time_source = runtime_components.time_source();
+        if let Some(identity) = self.cached_valid_identity(time_source.now()) {
+            return Future::ready(Ok(identity));
+        }
+
+        // Every concurrent caller that misses the cache calls the inner resolver here and
+        // builds its own `inner_future`, but only the one that wins the `refresh_lock`
+        // race below ever polls (awaits) it; the rest re-check the cache once they get
+        // the lock and, finding it repopulated, return without ever awaiting their own
+        // `inner_future`. Work the inner resolver does in `resolve_identity` itself,
+        // rather than in the returned future, is not covered by this single-flighting.
+        let inner_future = self.inner.resolve_identity(runtime_components, config_bag);
+        let this = self.clone();
+        Future::new(Box::pin(async move {
+            // Only one task actually refreshes; everyone else waits on this lock and
+            // then re-checks the cache rather than re-resolving.
+            let _permit = this.refresh_lock.lock().await;
+            if let Some(identity) = this.cached_valid_identity(time_source.now()) {
+                return Ok(identity);
+            }
+            let identity = inner_future.await?;
+            *this.cached.write().unwrap() = Some(identity.clone());
+            Ok(identity)
+        }))
+    }
+}
+
+/// Builder for [`CachingIdentityResolver`].
+#[derive(Debug)]
+pub struct Builder {
+    identity_resolver: SharedIdentityResolver,
+    refresh_buffer: Duration,
+}
+
+impl Builder {
+    /// Sets the amount of time before expiration that a cached identity is refreshed.
+    ///
+    /// Defaults to 1 minute.
+    pub fn refresh_buffer(mut self, refresh_buffer: Duration) -> Self {
+        self.refresh_buffer = refresh_buffer;
+        self
+    }
+
+    /// Builds the [`CachingIdentityResolver`].
+    pub fn build(self) -> CachingIdentityResolver {
+        CachingIdentityResolver {
+            inner: self.identity_resolver,
+            refresh_buffer: self.refresh_buffer,
+            cached: Arc::new(RwLock::new(None)),
+            refresh_lock: Arc::new(AsyncMutex::new(())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::identity::ConfigBagOnlyIdentityResolver;
+    use aws_smithy_async::time::StaticTimeSource;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug)]
+    struct CountingResolver {
+        calls: Arc<AtomicUsize>,
+        expiration: Option<SystemTime>,
+    }
+
+    // Exercises the `ConfigBagOnlyIdentityResolver` migration shim rather than
+    // implementing `IdentityResolver` directly.
+    impl ConfigBagOnlyIdentityResolver for CountingResolver {
+        fn resolve_identity(&self, _config_bag: &ConfigBag) -> Future<Identity> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Future::ready(Ok(Identity::new("secret", self.expiration)))
+        }
+    }
+
+    #[tokio::test]
+    async fn caches_identity_until_expiration() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let inner = CountingResolver {
+            calls: calls.clone(),
+            expiration: Some(SystemTime::now() + Duration::from_secs(300)),
+        };
+        let resolver =
+            CachingIdentityResolver::builder(SharedIdentityResolver::new(inner)).build();
+        let runtime_components = RuntimeComponents::builder("test").build().unwrap();
+        let config_bag = ConfigBag::base();
+
+        resolver
+            .resolve_identity(&runtime_components, &config_bag)
+            .await
+            .unwrap();
+        resolver
+            .resolve_identity(&runtime_components, &config_bag)
+            .await
+            .unwrap();
+        resolver
+            .resolve_identity(&runtime_components, &config_bag)
+            .await
+            .unwrap();
+
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn refreshes_once_expired() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let inner = CountingResolver {
+            calls: calls.clone(),
+            expiration: Some(SystemTime::now() - Duration::from_secs(1)),
+        };
+        let resolver =
+            CachingIdentityResolver::builder(SharedIdentityResolver::new(inner)).build();
+        let runtime_components = RuntimeComponents::builder("test").build().unwrap();
+        let config_bag = ConfigBag::base();
+
+        resolver
+            .resolve_identity(&runtime_components, &config_bag)
+            .await
+            .unwrap();
+        resolver
+            .resolve_identity(&runtime_components, &config_bag)
+            .await
+            .unwrap();
+
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn caches_identities_without_expiration_indefinitely() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let inner = CountingResolver {
+            calls: calls.clone(),
+            expiration: None,
+        };
+        let resolver =
+            CachingIdentityResolver::builder(SharedIdentityResolver::new(inner)).build();
+        let runtime_components = RuntimeComponents::builder("test").build().unwrap();
+        let config_bag = ConfigBag::base();
+
+        resolver
+            .resolve_identity(&runtime_components, &config_bag)
+            .await
+            .unwrap();
+        resolver
+            .resolve_identity(&runtime_components, &config_bag)
+            .await
+            .unwrap();
+
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn expiry_is_driven_by_the_configured_time_source_not_the_real_clock() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        // Already expired by the real wall clock...
+        let expiration = SystemTime::now() - Duration::from_secs(3600);
+        let inner = CountingResolver {
+            calls: calls.clone(),
+            expiration: Some(expiration),
+        };
+        let resolver =
+            CachingIdentityResolver::builder(SharedIdentityResolver::new(inner)).build();
+
+        // ...but according to this fake time source, it's still an hour from expiring.
+        let fake_now = expiration - Duration::from_secs(3600);
+        let runtime_components = RuntimeComponents::builder("test")
+            .with_time_source(Some(SharedTimeSource::new(StaticTimeSource::new(fake_now))))
+            .build()
+            .unwrap();
+        let config_bag = ConfigBag::base();
+
+        resolver
+            .resolve_identity(&runtime_components, &config_bag)
+            .await
+            .unwrap();
+        resolver
+            .resolve_identity(&runtime_components, &config_bag)
+            .await
+            .unwrap();
+
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+    }
+
+    /// An inner resolver that counts only the resolutions it actually carries out
+    /// (i.e. once its returned future is polled), and doesn't complete until `gate` is
+    /// notified. This lets a test hold a refresh in flight while other callers race in
+    /// behind it, to prove they single-flight onto the same refresh.
+    #[derive(Debug)]
+    struct BlockingResolver {
+        calls: Arc<AtomicUsize>,
+        gate: Arc<tokio::sync::Notify>,
+    }
+
+    impl ConfigBagOnlyIdentityResolver for BlockingResolver {
+        fn resolve_identity(&self, _config_bag: &ConfigBag) -> Future<Identity> {
+            let calls = self.calls.clone();
+            let gate = self.gate.clone();
+            Future::new(Box::pin(async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                gate.notified().await;
+                Ok(Identity::new("secret", None))
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_callers_single_flight_onto_one_refresh() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let gate = Arc::new(tokio::sync::Notify::new());
+        let inner = BlockingResolver {
+            calls: calls.clone(),
+            gate: gate.clone(),
+        };
+        let resolver = CachingIdentityResolver::builder(SharedIdentityResolver::new(inner)).build();
+        let runtime_components = Arc::new(RuntimeComponents::builder("test").build().unwrap());
+        let config_bag = Arc::new(ConfigBag::base());
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let resolver = resolver.clone();
+                let runtime_components = runtime_components.clone();
+                let config_bag = config_bag.clone();
+                tokio::spawn(async move {
+                    resolver
+                        .resolve_identity(&runtime_components, &config_bag)
+                        .await
+                })
+            })
+            .collect();
+
+        // Let every task reach the refresh lock (or the resolver's gate) before letting
+        // the one that won the race finish its refresh.
+        tokio::task::yield_now().await;
+        gate.notify_one();
+
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+    }
+}
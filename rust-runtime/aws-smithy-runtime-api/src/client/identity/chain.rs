@@ -0,0 +1,152 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A fallback chain of [`IdentityResolver`] implementations.
+
+use crate::box_error::BoxError;
+use crate::client::identity::{Identity, IdentityResolver, SharedIdentityResolver};
+use crate::client::orchestrator::Future;
+use crate::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::ConfigBag;
+use std::fmt;
+
+/// An [`IdentityResolver`] that tries a series of resolvers in order, returning the
+/// first successfully resolved [`Identity`].
+///
+/// This allows a single auth scheme to be backed by, for example, "environment
+/// credentials, then profile credentials, then instance metadata credentials": each
+/// link in the chain is tried in registration order, and the chain only fails if every
+/// link fails.
+#[derive(Clone, Debug)]
+pub struct ChainIdentityResolver {
+    resolvers: Vec<SharedIdentityResolver>,
+}
+
+impl ChainIdentityResolver {
+    /// Creates a new [`ChainIdentityResolver`] that tries `resolvers` in order.
+    pub fn new(resolvers: impl IntoIterator<Item = SharedIdentityResolver>) -> Self {
+        Self {
+            resolvers: resolvers.into_iter().collect(),
+        }
+    }
+}
+
+impl IdentityResolver for ChainIdentityResolver {
+    fn resolve_identity(
+        &self,
+        runtime_components: &RuntimeComponents,
+        config_bag: &ConfigBag,
+    ) -> Future<Identity> {
+        // `resolve_identity` borrows `runtime_components` and `config_bag`, but the
+        // `Future` we return must be `'static` so it can be boxed and awaited later by
+        // the caller. Build each link's future up front, while those borrows are still
+        // alive, so the `async move` block only has to own the futures themselves.
+        let futures: Vec<_> = self
+            .resolvers
+            .iter()
+            .map(|resolver| resolver.resolve_identity(runtime_components, config_bag))
+            .collect();
+        Future::new(Box::pin(async move {
+            let mut failures = Vec::new();
+            for future in futures {
+                match future.await {
+                    Ok(identity) => return Ok(identity),
+                    Err(err) => failures.push(err),
+                }
+            }
+            Err(ChainError(failures).into())
+        }))
+    }
+}
+
+/// The error returned when every link of a [`ChainIdentityResolver`] fails to resolve
+/// an identity. Reports the failure from each link so the root cause isn't hidden
+/// behind whichever resolver happened to run last.
+#[derive(Debug)]
+struct ChainError(Vec<BoxError>);
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "no identity resolver in the chain resolved an identity:")?;
+        for (index, failure) in self.0.iter().enumerate() {
+            writeln!(f, "  resolver #{index}: {failure}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::identity::ConfigBagOnlyIdentityResolver;
+    use std::fmt;
+
+    #[derive(Debug)]
+    struct FailingResolver(&'static str);
+
+    impl ConfigBagOnlyIdentityResolver for FailingResolver {
+        fn resolve_identity(&self, _config_bag: &ConfigBag) -> Future<Identity> {
+            #[derive(Debug)]
+            struct Failure(&'static str);
+            impl fmt::Display for Failure {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "{}", self.0)
+                }
+            }
+            impl std::error::Error for Failure {}
+
+            Future::ready(Err(Box::new(Failure(self.0)).into()))
+        }
+    }
+
+    #[derive(Debug)]
+    struct SucceedingResolver;
+
+    impl ConfigBagOnlyIdentityResolver for SucceedingResolver {
+        fn resolve_identity(&self, _config_bag: &ConfigBag) -> Future<Identity> {
+            Future::ready(Ok(Identity::new("identity-from-chain", None)))
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_first_successful_identity() {
+        let chain = ChainIdentityResolver::new([
+            SharedIdentityResolver::new(FailingResolver("first")),
+            SharedIdentityResolver::new(SucceedingResolver),
+            SharedIdentityResolver::new(FailingResolver("never reached")),
+        ]);
+        let runtime_components = RuntimeComponents::builder("test").build().unwrap();
+        let config_bag = ConfigBag::base();
+
+        let identity = chain
+            .resolve_identity(&runtime_components, &config_bag)
+            .await
+            .unwrap();
+        assert_eq!(
+            Some(&"identity-from-chain"),
+            identity.data::<&str>()
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_with_aggregated_error_when_every_link_fails() {
+        let chain = ChainIdentityResolver::new([
+            SharedIdentityResolver::new(FailingResolver("first")),
+            SharedIdentityResolver::new(FailingResolver("second")),
+        ]);
+        let runtime_components = RuntimeComponents::builder("test").build().unwrap();
+        let config_bag = ConfigBag::base();
+
+        let err = chain
+            .resolve_identity(&runtime_components, &config_bag)
+            .await
+            .unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("first"));
+        assert!(message.contains("second"));
+    }
+}
@@ -0,0 +1,57 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A zeroize-on-drop, redacted-`Debug` secret string for use as [`Identity`](super::Identity) data.
+
+use std::fmt;
+use zeroize::Zeroizing;
+
+/// A secret string whose contents are zeroed on drop and never printed via `Debug`.
+///
+/// Identity resolvers for auth schemes like `@httpApiKeyAuth` and `@httpBearerAuth` should
+/// wrap their token/key material in a `SecretString` (for example via
+/// [`Identity::new_secret`](super::Identity::new_secret)) instead of storing a bare `String`,
+/// so that secrets never show up in logs or error messages and are scrubbed from memory as
+/// soon as they're dropped.
+#[derive(Clone)]
+pub struct SecretString(Zeroizing<String>);
+
+impl SecretString {
+    /// Creates a new `SecretString` wrapping the given value.
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self(Zeroizing::new(secret.into()))
+    }
+
+    /// Returns the secret value.
+    ///
+    /// Prefer to use this only where the value must be sent over the wire, and avoid
+    /// logging, storing, or otherwise persisting the returned `&str`.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "** redacted **")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_output_is_redacted() {
+        let secret = SecretString::new("super-secret-token");
+        assert_eq!("** redacted **", format!("{:?}", secret));
+    }
+
+    #[test]
+    fn exposes_the_underlying_secret() {
+        let secret = SecretString::new("super-secret-token");
+        assert_eq!("super-secret-token", secret.expose_secret());
+    }
+}
@@ -0,0 +1,177 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Config-time validation for registered identity resolvers.
+
+use crate::box_error::BoxError;
+use crate::client::auth::AuthSchemeId;
+use crate::client::identity::IdentityResolvers;
+use crate::client::runtime_components::RuntimeComponents;
+use crate::client::runtime_plugin::RuntimePlugin;
+use aws_smithy_types::config_bag::ConfigBag;
+use std::fmt;
+
+/// A [`RuntimePlugin`] that validates, at client config build time, that every auth
+/// scheme the client can select has a registered identity resolver.
+///
+/// A client builder adds this to its list of runtime plugins alongside whatever
+/// `AuthSchemeId`s the service's auth scheme resolver can select; it's run through
+/// [`crate::client::runtime_plugin::validate_base_client_config`] once the config is
+/// fully assembled. Without it, a missing resolver only ever surfaced as the
+/// `tracing::warn!("no identity resolvers available")` inside `IdentityResolvers::new`,
+/// and the resulting auth failure wasn't visible until the first request was dispatched.
+#[derive(Debug)]
+pub struct IdentityResolversValidator {
+    auth_scheme_ids: Vec<AuthSchemeId>,
+}
+
+impl IdentityResolversValidator {
+    /// Creates a new `IdentityResolversValidator` that checks `auth_scheme_ids` — the
+    /// full set of auth schemes the client's auth scheme resolver can select — against
+    /// the `IdentityResolvers` registered in the config bag.
+    pub fn new(auth_scheme_ids: impl IntoIterator<Item = AuthSchemeId>) -> Self {
+        Self {
+            auth_scheme_ids: auth_scheme_ids.into_iter().collect(),
+        }
+    }
+}
+
+impl RuntimePlugin for IdentityResolversValidator {
+    fn validate_base_client_config(
+        &self,
+        _runtime_components: &RuntimeComponents,
+        config_bag: &ConfigBag,
+    ) -> Result<(), BoxError> {
+        // If no `IdentityResolvers` was ever registered, every selectable scheme is
+        // unresolvable — that's a more useful diagnostic than panicking, and it's the
+        // same failure a caller would eventually see, just surfaced eagerly instead.
+        let unresolvable: Vec<AuthSchemeId> = match config_bag.load::<IdentityResolvers>() {
+            Some(identity_resolvers) => self
+                .auth_scheme_ids
+                .iter()
+                .copied()
+                .filter(|scheme_id| identity_resolvers.identity_resolver(*scheme_id).is_none())
+                .collect(),
+            None => self.auth_scheme_ids.clone(),
+        };
+
+        if unresolvable.is_empty() {
+            Ok(())
+        } else {
+            Err(MissingIdentityResolverError(unresolvable).into())
+        }
+    }
+}
+
+/// An auth scheme the client can select has no registered identity resolver.
+#[derive(Debug)]
+struct MissingIdentityResolverError(Vec<AuthSchemeId>);
+
+impl fmt::Display for MissingIdentityResolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for scheme_id in &self.0 {
+            writeln!(
+                f,
+                "auth scheme `{scheme_id:?}` selected but no identity resolver registered for it"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MissingIdentityResolverError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::identity::{
+        ConfigBagOnlyIdentityResolver, ConfiguredIdentityResolver, Identity,
+        SharedIdentityResolver,
+    };
+    use crate::client::orchestrator::Future;
+    use crate::client::runtime_plugin::validate_base_client_config;
+    use aws_smithy_types::config_bag::Layer;
+
+    #[derive(Debug)]
+    struct StubResolver;
+    impl ConfigBagOnlyIdentityResolver for StubResolver {
+        fn resolve_identity(&self, _config_bag: &ConfigBag) -> Future<Identity> {
+            Future::ready(Ok(Identity::new("stub", None)))
+        }
+    }
+
+    fn config_bag_with(identity_resolvers: IdentityResolvers) -> ConfigBag {
+        let mut layer = Layer::new("identity-resolvers-validator-test");
+        layer.store_put(identity_resolvers);
+        ConfigBag::of_layers(vec![layer])
+    }
+
+    #[test]
+    fn error_message_names_every_unresolvable_scheme() {
+        let error = MissingIdentityResolverError(vec![
+            AuthSchemeId::new("sigv4"),
+            AuthSchemeId::new("http-bearer-auth"),
+        ]);
+        let message = error.to_string();
+        assert!(message.contains("sigv4"));
+        assert!(message.contains("http-bearer-auth"));
+    }
+
+    #[test]
+    fn fails_config_build_when_a_selectable_scheme_has_no_resolver() {
+        let sigv4 = AuthSchemeId::new("sigv4");
+        let http_bearer_auth = AuthSchemeId::new("http-bearer-auth");
+        let identity_resolvers = IdentityResolvers::new(
+            [ConfiguredIdentityResolver::new(
+                sigv4,
+                SharedIdentityResolver::new(StubResolver),
+            )]
+            .iter(),
+        );
+        let config_bag = config_bag_with(identity_resolvers);
+        let runtime_components = RuntimeComponents::builder("test").build().unwrap();
+        let plugins: Vec<Box<dyn RuntimePlugin>> =
+            vec![Box::new(IdentityResolversValidator::new([
+                sigv4,
+                http_bearer_auth,
+            ]))];
+
+        let err = validate_base_client_config(&plugins, &runtime_components, &config_bag)
+            .expect_err("http-bearer-auth has no registered identity resolver");
+        assert!(err.to_string().contains("http-bearer-auth"));
+    }
+
+    #[test]
+    fn passes_config_build_when_every_selectable_scheme_has_a_resolver() {
+        let sigv4 = AuthSchemeId::new("sigv4");
+        let identity_resolvers = IdentityResolvers::new(
+            [ConfiguredIdentityResolver::new(
+                sigv4,
+                SharedIdentityResolver::new(StubResolver),
+            )]
+            .iter(),
+        );
+        let config_bag = config_bag_with(identity_resolvers);
+        let runtime_components = RuntimeComponents::builder("test").build().unwrap();
+        let plugins: Vec<Box<dyn RuntimePlugin>> =
+            vec![Box::new(IdentityResolversValidator::new([sigv4]))];
+
+        assert!(validate_base_client_config(&plugins, &runtime_components, &config_bag).is_ok());
+    }
+
+    #[test]
+    fn fails_with_an_error_rather_than_panicking_when_no_identity_resolvers_are_registered() {
+        let sigv4 = AuthSchemeId::new("sigv4");
+        // No `IdentityResolvers` layer was ever pushed onto this config bag.
+        let config_bag = ConfigBag::base();
+        let runtime_components = RuntimeComponents::builder("test").build().unwrap();
+        let plugins: Vec<Box<dyn RuntimePlugin>> =
+            vec![Box::new(IdentityResolversValidator::new([sigv4]))];
+
+        let err = validate_base_client_config(&plugins, &runtime_components, &config_bag)
+            .expect_err("sigv4 has no registered identity resolver");
+        assert!(err.to_string().contains("sigv4"));
+    }
+}
@@ -0,0 +1,108 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Extension point for plugins that validate a client's assembled configuration.
+
+use crate::box_error::BoxError;
+use crate::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::ConfigBag;
+use std::fmt;
+
+/// A plugin that takes part in building a client's configuration.
+///
+/// Its only hook is [`RuntimePlugin::validate_base_client_config`]; this trait has no
+/// method for contributing config. A client builder collects its `RuntimePlugin`s and,
+/// once the `RuntimeComponents`/`ConfigBag` have been assembled, calls
+/// [`validate_base_client_config`] on the whole set before handing the finished client
+/// back to the caller.
+pub trait RuntimePlugin: Send + Sync + fmt::Debug {
+    /// Validates the fully assembled base client config.
+    ///
+    /// The default implementation does nothing. Plugins that need to catch
+    /// misconfiguration eagerly, rather than have it surface as a runtime request
+    /// failure, override this.
+    fn validate_base_client_config(
+        &self,
+        runtime_components: &RuntimeComponents,
+        config_bag: &ConfigBag,
+    ) -> Result<(), BoxError> {
+        let _ = (runtime_components, config_bag);
+        Ok(())
+    }
+}
+
+/// Runs [`RuntimePlugin::validate_base_client_config`] on every plugin in `plugins`, in
+/// order, stopping at (and returning) the first error.
+///
+/// A client builder calls this once, after every plugin has contributed its
+/// `RuntimeComponents` and config, before the client is returned to the caller, so that
+/// misconfiguration is caught at config-build time instead of at request time.
+pub fn validate_base_client_config(
+    plugins: &[Box<dyn RuntimePlugin>],
+    runtime_components: &RuntimeComponents,
+    config_bag: &ConfigBag,
+) -> Result<(), BoxError> {
+    for plugin in plugins {
+        plugin.validate_base_client_config(runtime_components, config_bag)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct AlwaysFails;
+    impl RuntimePlugin for AlwaysFails {
+        fn validate_base_client_config(
+            &self,
+            _runtime_components: &RuntimeComponents,
+            _config_bag: &ConfigBag,
+        ) -> Result<(), BoxError> {
+            Err("this plugin always fails validation".into())
+        }
+    }
+
+    #[derive(Debug)]
+    struct AlwaysSucceeds;
+    impl RuntimePlugin for AlwaysSucceeds {}
+
+    #[derive(Debug)]
+    struct PanicsIfRun;
+    impl RuntimePlugin for PanicsIfRun {
+        fn validate_base_client_config(
+            &self,
+            _runtime_components: &RuntimeComponents,
+            _config_bag: &ConfigBag,
+        ) -> Result<(), BoxError> {
+            panic!("validation should have stopped at the earlier failure")
+        }
+    }
+
+    #[test]
+    fn runs_every_plugin_and_stops_at_the_first_failure() {
+        let plugins: Vec<Box<dyn RuntimePlugin>> = vec![
+            Box::new(AlwaysSucceeds),
+            Box::new(AlwaysFails),
+            Box::new(PanicsIfRun),
+        ];
+        let runtime_components = RuntimeComponents::builder("test").build().unwrap();
+        let config_bag = ConfigBag::base();
+
+        let result = validate_base_client_config(&plugins, &runtime_components, &config_bag);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn succeeds_when_every_plugin_succeeds() {
+        let plugins: Vec<Box<dyn RuntimePlugin>> = vec![Box::new(AlwaysSucceeds)];
+        let runtime_components = RuntimeComponents::builder("test").build().unwrap();
+        let config_bag = ConfigBag::base();
+
+        let result = validate_base_client_config(&plugins, &runtime_components, &config_bag);
+        assert!(result.is_ok());
+    }
+}